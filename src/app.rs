@@ -0,0 +1,63 @@
+// [Motivation] The sync server (the ThreadPool accept loop in `main`) and the
+// async server (`async_server`) dispatch connections in incompatible ways —
+// one blocks a worker thread per request via the synchronous `Router`, the
+// other must park a `Future` instead so a sleeping request doesn't tie up a
+// Tokio worker — so they can't share a handler. What they share is the route
+// *data* below (which path sleeps, for how long, which file each route
+// serves), so the two can't silently drift onto different behavior even
+// though each drives its own I/O.
+use crate::http::{Method, Request, Response};
+use crate::router::Router;
+use crate::static_files::StaticFiles;
+use std::{fs, net::TcpStream, thread, time::Duration};
+
+pub const INDEX_FILE: &str = "hello.html";
+pub const SLEEP_PATH: &str = "/sleep";
+pub const SLEEP_DURATION: Duration = Duration::from_secs(5);
+
+pub fn build_router() -> Router {
+    let mut router = Router::new();
+
+    router.route(Method::Get, "/", |_request| {
+        let contents = fs::read_to_string(INDEX_FILE).unwrap();
+        Response::new("HTTP/1.1 200 OK", contents.into_bytes())
+    });
+
+    router.route(Method::Get, SLEEP_PATH, |_request| {
+        // Simulating a slow response
+        // Server sleep for 5 seconds before rendering the successful HTML page.
+        thread::sleep(SLEEP_DURATION);
+        let contents = fs::read_to_string(INDEX_FILE).unwrap();
+        Response::new("HTTP/1.1 200 OK", contents.into_bytes())
+    });
+
+    router
+}
+
+pub fn build_static_files() -> StaticFiles {
+    StaticFiles::new(".").expect("document root \".\" should be accessible")
+}
+
+// Parses a request off `stream`, dispatches it through `router` (falling
+// back to `static_files` for anything unregistered), and writes the
+// response back. Shared by both server modes so a connection is handled
+// identically regardless of which one accepted it.
+pub fn handle_connection(stream: &mut TcpStream, router: &Router, static_files: &StaticFiles) {
+    let request = match Request::parse(stream) {
+        Ok(request) => request,
+        Err(err) => {
+            println!("Failed to parse request: {err}");
+            return;
+        }
+    };
+
+    let response = router
+        .handle(&request)
+        .unwrap_or_else(|| static_files.serve(&request));
+
+    println!("{:?} {} -> {}", request.method, request.path, response.status_line);
+
+    if let Err(err) = response.write_to(stream) {
+        println!("Failed to write response: {err}");
+    }
+}
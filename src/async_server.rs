@@ -0,0 +1,196 @@
+// [Motivation] The hand-rolled `ThreadPool` ties up one OS thread per
+// in-flight job, so a handler that's merely waiting (like `/sleep`) still
+// occupies a whole worker. This module is an alternative server mode, gated
+// behind the `async` cargo feature, that drives connections cooperatively on
+// Tokio instead, so a sleeping request only parks a future.
+//
+// It can't reuse `crate::app::handle_connection` — that dispatches through
+// the synchronous `Router`, which would have to run on a blocking-task
+// thread and defeat the point of this module. It also serves arbitrary
+// files under the document root, same as the sync server's `StaticFiles`
+// fallback: same traversal protection (`static_files::is_within_root`, just
+// applied to a `tokio::fs::canonicalize`d path instead of a blocking one)
+// and the same MIME detection (`static_files::content_type_for`), so
+// flipping on this feature doesn't silently drop file serving for anything
+// but `hello.html`/`404.html`.
+use crate::app::{self, INDEX_FILE, SLEEP_PATH};
+use crate::static_files::{content_type_for, is_within_root, StaticFiles};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::{
+    fs,
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+    sync::Semaphore,
+    time::sleep,
+};
+
+// Accepts connections forever, handing each one to its own task but never
+// running more than `concurrency_limit` of them at once. `address` takes
+// anything `TcpListener::bind` does (a `&str` literal or an owned `String`
+// discovered at runtime, e.g. an ephemeral port a test asked the OS for).
+pub async fn run(address: impl tokio::net::ToSocketAddrs, concurrency_limit: usize) -> std::io::Result<()> {
+    let listener = TcpListener::bind(address).await?;
+    let semaphore = Arc::new(Semaphore::new(concurrency_limit));
+    let static_files = Arc::new(app::build_static_files());
+
+    loop {
+        let (stream, _addr) = listener.accept().await?;
+        let permit = Arc::clone(&semaphore).acquire_owned().await.unwrap();
+        let static_files = Arc::clone(&static_files);
+
+        tokio::spawn(async move {
+            handle_connection(stream, &static_files).await;
+            drop(permit);
+        });
+    }
+}
+
+async fn handle_connection(mut stream: TcpStream, static_files: &StaticFiles) {
+    let mut reader = BufReader::new(&mut stream);
+    let mut request_line = String::new();
+    match reader.read_line(&mut request_line).await {
+        Ok(0) | Err(_) => return, // connection closed before sending a request line
+        Ok(_) => {}
+    }
+    let request_line = request_line.trim_end().to_string();
+
+    // Drain the header lines even though this mode doesn't use them, same as
+    // a real client expects: the connection would otherwise be left with
+    // unread header bytes still sitting in front of the next request.
+    loop {
+        let mut line = String::new();
+        match reader.read_line(&mut line).await {
+            Ok(0) | Err(_) => return,
+            Ok(_) => {}
+        }
+        if line.trim_end().is_empty() {
+            break;
+        }
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let (method, path) = match (parts.next(), parts.next()) {
+        (Some(method), Some(path)) => (method, path),
+        _ => return,
+    };
+
+    let (status_line, content_type, body) = if method != "GET" {
+        ("HTTP/1.1 405 METHOD NOT ALLOWED", "text/html; charset=UTF-8", Vec::new())
+    } else if path == SLEEP_PATH {
+        // Cooperative sleep: this only parks the current task, leaving the
+        // runtime's worker threads free to drive every other in-flight
+        // connection.
+        sleep(app::SLEEP_DURATION).await;
+        index_response().await
+    } else if path == "/" {
+        index_response().await
+    } else {
+        serve_static(static_files, path).await
+    };
+
+    let response = format!(
+        "{status_line}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\n\r\n",
+        body.len()
+    );
+
+    if let Err(err) = stream.write_all(response.as_bytes()).await {
+        println!("Failed to write response: {err}");
+        return;
+    }
+    if let Err(err) = stream.write_all(&body).await {
+        println!("Failed to write response: {err}");
+    }
+}
+
+async fn index_response() -> (&'static str, &'static str, Vec<u8>) {
+    match fs::read(INDEX_FILE).await {
+        Ok(contents) => ("HTTP/1.1 200 OK", content_type_for(Path::new(INDEX_FILE)), contents),
+        Err(_) => ("HTTP/1.1 404 NOT FOUND", "text/html; charset=UTF-8", Vec::new()),
+    }
+}
+
+// Resolves `path` under `static_files`'s document root the same way
+// `StaticFiles::serve` does — reject anything that canonicalizes outside the
+// root — except every filesystem call is the async `tokio::fs` equivalent,
+// so a slow disk read doesn't block this task's worker thread either.
+async fn serve_static(static_files: &StaticFiles, path: &str) -> (&'static str, &'static str, Vec<u8>) {
+    let relative = path.trim_start_matches('/');
+    let candidate = static_files.root().join(relative);
+
+    let resolved = match fs::canonicalize(&candidate).await {
+        Ok(resolved) => resolved,
+        Err(_) => return not_found(static_files).await,
+    };
+
+    if !is_within_root(static_files.root(), &resolved) {
+        return ("HTTP/1.1 403 FORBIDDEN", "text/html; charset=UTF-8", Vec::new());
+    }
+
+    match fs::read(&resolved).await {
+        Ok(contents) => ("HTTP/1.1 200 OK", content_type_for(&resolved), contents),
+        Err(_) => not_found(static_files).await,
+    }
+}
+
+async fn not_found(static_files: &StaticFiles) -> (&'static str, &'static str, Vec<u8>) {
+    let body = fs::read(not_found_path(static_files))
+        .await
+        .unwrap_or_default();
+    ("HTTP/1.1 404 NOT FOUND", "text/html; charset=UTF-8", body)
+}
+
+fn not_found_path(static_files: &StaticFiles) -> PathBuf {
+    static_files.root().join("404.html")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+    use tokio::io::AsyncReadExt;
+
+    // Connects, sends a `/sleep` request, and waits for the connection to
+    // close, returning how long the whole round trip took.
+    async fn sleep_request(addr: String) -> Duration {
+        let start = Instant::now();
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream.write_all(b"GET /sleep HTTP/1.1\r\nHost: x\r\n\r\n").await.unwrap();
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response).await.unwrap();
+        start.elapsed()
+    }
+
+    #[tokio::test]
+    async fn bounds_concurrent_sleeps_to_the_configured_limit() {
+        // Grab a free port from the OS instead of hardcoding one, same as
+        // the sync server's tests do, so this doesn't collide with another
+        // process or test run bound to a fixed port.
+        let addr = TcpListener::bind("127.0.0.1:0").await.unwrap().local_addr().unwrap().to_string();
+        let server = tokio::spawn(run(addr.clone(), 2));
+        tokio::time::sleep(Duration::from_millis(100)).await; // let the listener bind
+        assert!(!server.is_finished(), "server task exited early, probably failed to bind");
+
+        let start = Instant::now();
+        let requests: Vec<_> = (0..4).map(|_| tokio::spawn(sleep_request(addr.clone()))).collect();
+        for request in requests {
+            request.await.unwrap();
+        }
+        let elapsed = start.elapsed();
+
+        // 4 requests bounded to 2 at a time run as two waves of SLEEP_DURATION
+        // each (~10s) — proving the semaphore actually bounds concurrency,
+        // rather than either all 4 sleeping in parallel (~5s, no bound at all)
+        // or all 4 serialized one at a time (~20s, no concurrency at all).
+        assert!(
+            elapsed >= app::SLEEP_DURATION * 2,
+            "expected at least two waves of {:?}, got {elapsed:?}",
+            app::SLEEP_DURATION
+        );
+        assert!(
+            elapsed < app::SLEEP_DURATION * 3,
+            "expected fewer than four serialized waves of {:?}, got {elapsed:?}",
+            app::SLEEP_DURATION
+        );
+    }
+}
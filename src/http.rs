@@ -0,0 +1,225 @@
+// [Motivation] `handle_connection` used to string-match the raw request line
+// (`"GET / HTTP/1.1"`). That breaks the moment a client sends headers, a
+// body, or a method we haven't hardcoded. This module turns the raw
+// `TcpStream` into a structured `Request`, and gives handlers a `Response`
+// type to build replies with.
+use std::{
+    collections::HashMap,
+    fmt,
+    io::{prelude::*, BufReader},
+    net::TcpStream,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Method {
+    Get,
+    Post,
+    Put,
+    Delete,
+    Head,
+    Options,
+    Patch,
+}
+
+impl Method {
+    fn parse(s: &str) -> Option<Method> {
+        match s {
+            "GET" => Some(Method::Get),
+            "POST" => Some(Method::Post),
+            "PUT" => Some(Method::Put),
+            "DELETE" => Some(Method::Delete),
+            "HEAD" => Some(Method::Head),
+            "OPTIONS" => Some(Method::Options),
+            "PATCH" => Some(Method::Patch),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Request {
+    pub method: Method,
+    pub path: String,
+    pub version: String,
+    pub headers: HashMap<String, String>,
+    pub body: Vec<u8>,
+}
+
+#[derive(Debug)]
+pub enum RequestParseError {
+    UnexpectedEof,
+    MalformedRequestLine(String),
+    UnsupportedMethod(String),
+    Io(std::io::Error),
+}
+
+impl fmt::Display for RequestParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RequestParseError::UnexpectedEof => write!(f, "connection closed before a request line was sent"),
+            RequestParseError::MalformedRequestLine(line) => {
+                write!(f, "malformed request line: {line:?}")
+            }
+            RequestParseError::UnsupportedMethod(method) => write!(f, "unsupported method: {method}"),
+            RequestParseError::Io(err) => write!(f, "failed to read request: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for RequestParseError {}
+
+impl Request {
+    // Reads a request line, headers, and (if `Content-Length` is present) a
+    // body out of `stream`. Wrapping `stream` in a `BufReader` here and
+    // dropping it before returning leaves the underlying `TcpStream` free for
+    // the caller to write a `Response` back on.
+    pub fn parse(stream: &mut TcpStream) -> Result<Request, RequestParseError> {
+        let mut reader = BufReader::new(stream);
+
+        let mut request_line = String::new();
+        reader
+            .read_line(&mut request_line)
+            .map_err(RequestParseError::Io)?;
+        if request_line.is_empty() {
+            return Err(RequestParseError::UnexpectedEof);
+        }
+        let request_line = request_line.trim_end();
+
+        let mut parts = request_line.split_whitespace();
+        let method = parts
+            .next()
+            .ok_or_else(|| RequestParseError::MalformedRequestLine(request_line.to_string()))?;
+        let path = parts
+            .next()
+            .ok_or_else(|| RequestParseError::MalformedRequestLine(request_line.to_string()))?;
+        let version = parts
+            .next()
+            .ok_or_else(|| RequestParseError::MalformedRequestLine(request_line.to_string()))?;
+
+        let method = Method::parse(method)
+            .ok_or_else(|| RequestParseError::UnsupportedMethod(method.to_string()))?;
+
+        let mut headers = HashMap::new();
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).map_err(RequestParseError::Io)?;
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':') {
+                headers.insert(name.trim().to_string(), value.trim().to_string());
+            }
+        }
+
+        let body = match headers.get("Content-Length").and_then(|len| len.parse::<usize>().ok()) {
+            Some(len) => {
+                let mut body = vec![0; len];
+                reader.read_exact(&mut body).map_err(RequestParseError::Io)?;
+                body
+            }
+            None => Vec::new(),
+        };
+
+        Ok(Request {
+            method,
+            path: path.to_string(),
+            version: version.to_string(),
+            headers,
+            body,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct Response {
+    pub status_line: String,
+    pub headers: HashMap<String, String>,
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    // Defaults Content-Type to text/html since that's every handler's
+    // response today; callers that need something else use `with_header`.
+    pub fn new(status_line: impl Into<String>, body: Vec<u8>) -> Response {
+        let mut headers = HashMap::new();
+        headers.insert(
+            "Content-Type".to_string(),
+            "text/html; charset=UTF-8".to_string(),
+        );
+
+        Response {
+            status_line: status_line.into(),
+            headers,
+            body,
+        }
+    }
+
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Response {
+        self.headers.insert(name.into(), value.into());
+        self
+    }
+
+    pub fn write_to(&self, stream: &mut TcpStream) -> std::io::Result<()> {
+        let mut head = format!("{}\r\n", self.status_line);
+        for (name, value) in &self.headers {
+            head.push_str(&format!("{name}: {value}\r\n"));
+        }
+        head.push_str(&format!("Content-Length: {}\r\n\r\n", self.body.len()));
+
+        stream.write_all(head.as_bytes())?;
+        stream.write_all(&self.body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+
+    // `Request::parse` reads off a real `TcpStream`, so these spin up a
+    // loopback listener, write raw request bytes from one end, and parse off
+    // the other — same as a real client/server pair, just both in-process.
+    fn parse(raw: &[u8]) -> Result<Request, RequestParseError> {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let mut client = TcpStream::connect(addr).unwrap();
+        client.write_all(raw).unwrap();
+
+        let mut server = listener.accept().unwrap().0;
+        Request::parse(&mut server)
+    }
+
+    #[test]
+    fn parses_method_path_and_headers() {
+        let request = parse(b"GET /foo?a=1 HTTP/1.1\r\nHost: example.com\r\nX-Custom: yes\r\n\r\n").unwrap();
+
+        assert_eq!(request.method, Method::Get);
+        assert_eq!(request.path, "/foo?a=1");
+        assert_eq!(request.version, "HTTP/1.1");
+        assert_eq!(request.headers.get("Host").map(String::as_str), Some("example.com"));
+        assert_eq!(request.headers.get("X-Custom").map(String::as_str), Some("yes"));
+        assert!(request.body.is_empty());
+    }
+
+    #[test]
+    fn reads_exactly_content_length_bytes_as_the_body() {
+        let request = parse(b"POST /submit HTTP/1.1\r\nContent-Length: 5\r\n\r\nhello").unwrap();
+
+        assert_eq!(request.method, Method::Post);
+        assert_eq!(request.body, b"hello");
+    }
+
+    #[test]
+    fn rejects_an_unsupported_method() {
+        let err = parse(b"TRACE / HTTP/1.1\r\n\r\n").unwrap_err();
+        assert!(matches!(err, RequestParseError::UnsupportedMethod(m) if m == "TRACE"));
+    }
+
+    #[test]
+    fn rejects_a_malformed_request_line() {
+        let err = parse(b"GET\r\n\r\n").unwrap_err();
+        assert!(matches!(err, RequestParseError::MalformedRequestLine(_)));
+    }
+}
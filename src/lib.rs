@@ -1,9 +1,20 @@
 use std::{
     fmt,
-    sync::{mpsc, Arc, Mutex},
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc, Arc, Mutex,
+    },
     thread,
 };
 
+pub mod app;
+#[cfg(feature = "async")]
+pub mod async_server;
+pub mod http;
+pub mod router;
+pub mod static_files;
+
 #[derive(Debug)] // Allows println!("{:?}", err); for debugging purposes.
 pub enum PoolCreationError {
     InvalidSize,                      // ThreadPool size must be greater than zero
@@ -30,40 +41,97 @@ impl std::error::Error for PoolCreationError {}
 
 pub struct Worker {
     id: usize,
-    thread: thread::JoinHandle<Arc<Mutex<mpsc::Receiver<Job>>>>,
+    thread: Option<thread::JoinHandle<()>>,
 }
 
 impl Worker {
     // Private because it's an implementation detail of the ThreadPool. Main does not need to know about it.
-    fn new(id: usize, receiver: Arc<Mutex<mpsc::Receiver<Job>>>) -> Result<Worker, std::io::Error> {
+    fn new(
+        id: usize,
+        receiver: Arc<Mutex<mpsc::Receiver<Message>>>,
+        stats: Arc<PoolStats>,
+    ) -> Result<Worker, std::io::Error> {
         let builder = thread::Builder::new().name(format!("worker-{}", id));
 
         // [Question] What does the code below do?
-        // It spawns a new thread that will receive jobs from the receiver and execute them.
+        // It spawns a new thread that loops, waiting for either a job to run or a
+        // signal to shut down. A `RecvError` means the sending half was dropped,
+        // i.e. there is no more work coming, so the loop breaks and the thread ends.
         let thread = builder.spawn(move || loop {
-            let job = receiver
+            let message = receiver
                 .lock() // Use to acquire a mutex, blocking the current thread until it is able to do so.
                 .expect("Failed to acquire mutex")
-                .recv() // Use to receive a job from the receiver
-                .unwrap(); // Use to unwrap the received job
+                .recv(); // Use to receive a message from the sender
 
-            println!("Worker {id} got a job; executing.");
+            match message {
+                Ok(Message::NewJob(job)) => {
+                    println!("Worker {id} got a job; executing.");
 
-            job();
+                    // A handler that panics shouldn't take the whole worker
+                    // thread down with it; catch the unwind so the loop can
+                    // go back to waiting for the next job.
+                    match panic::catch_unwind(AssertUnwindSafe(job)) {
+                        Ok(()) => {
+                            stats.jobs_completed.fetch_add(1, Ordering::Relaxed);
+                        }
+                        Err(payload) => {
+                            stats.panics_caught.fetch_add(1, Ordering::Relaxed);
+                            eprintln!("Worker {id} panicked while running a job: {}", panic_message(&payload));
+                        }
+                    }
+                }
+                Ok(Message::Terminate) => {
+                    println!("Worker {id} was told to terminate.");
+                    break;
+                }
+                Err(_) => {
+                    // Sender was dropped; nothing left to receive.
+                    break;
+                }
+            }
         })?;
 
         // If spawn succeeded, return the Worker
-        Ok(Worker { id, thread })
+        Ok(Worker {
+            id,
+            thread: Some(thread),
+        })
+    }
+}
+
+// Panic payloads are `Box<dyn Any>`, usually holding either a `&str` or a
+// `String`; fall back to a generic label for anything else (e.g. a custom
+// payload from `panic::panic_any`).
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message
+    } else {
+        "non-string panic payload"
     }
 }
 
+#[derive(Default)]
+struct PoolStats {
+    jobs_completed: AtomicUsize,
+    panics_caught: AtomicUsize,
+}
+
 pub struct ThreadPool {
-    workers: Vec<Worker>, // size = 24 (0x18), align = 0x8, offset = 0x10
-    sender: mpsc::Sender<Job>,
+    workers: Mutex<Vec<Worker>>,
+    sender: Option<mpsc::Sender<Message>>,
+    receiver: Arc<Mutex<mpsc::Receiver<Message>>>,
+    stats: Arc<PoolStats>,
 }
 
 type Job = Box<dyn FnOnce() + Send + 'static>;
 
+enum Message {
+    NewJob(Job),
+    Terminate,
+}
+
 impl ThreadPool {
     // Constructor for ThreadPool. Creates a new ThreadPool with the given +ve number (usize) of threads.
     pub fn build(size: usize) -> Result<ThreadPool, PoolCreationError> {
@@ -73,13 +141,19 @@ impl ThreadPool {
 
         let (sender, receiver) = mpsc::channel(); // [Note] Creates a new asynchronous channel, returning the sender/receiver halves.
         let receiver = Arc::new(Mutex::new(receiver)); // [Note] Arc is a thread-safe reference-counting pointer. ‘Arc’ stands for ‘Atomically Reference Counted’.
+        let stats = Arc::new(PoolStats::default());
 
         let workers = (0..size)
-            .map(|id| Worker::new(id, Arc::clone(&receiver)))
+            .map(|id| Worker::new(id, Arc::clone(&receiver), Arc::clone(&stats)))
             .collect::<Result<Vec<_>, std::io::Error>>()
             .map_err(PoolCreationError::ThreadSpawnError)?;
 
-        Ok(ThreadPool { workers, sender })
+        Ok(ThreadPool {
+            workers: Mutex::new(workers),
+            sender: Some(sender),
+            receiver,
+            stats,
+        })
     }
 
     // Method to execute a closure in the ThreadPool.
@@ -91,8 +165,173 @@ impl ThreadPool {
         // [Note] 'static lifetime is required to ensure the closure outlives the thread.
         F: FnOnce() + Send + 'static,
     {
+        self.ensure_capacity();
+
         // Implementation details...
         let job = Box::new(f); // Create a boxed closure
-        self.sender.send(job).unwrap(); // Send the job to the receiver
+        self.sender
+            .as_ref()
+            .expect("sender is only taken on drop")
+            .send(Message::NewJob(job))
+            .unwrap(); // Send the job to the receiver
+    }
+
+    // Replaces any worker whose thread has already exited so a single dead
+    // worker doesn't permanently shrink the pool's capacity. A job panic is
+    // already caught inside the worker loop and never reaches here; this
+    // covers the rest — a worker thread exiting some other way, e.g. a
+    // poisoned mutex or an explicit Message::Terminate reaching it directly.
+    fn ensure_capacity(&self) {
+        let mut workers = self.workers.lock().expect("Failed to acquire mutex");
+
+        for worker in workers.iter_mut() {
+            let dead = match &worker.thread {
+                Some(t) => t.is_finished(),
+                None => true,
+            };
+
+            if !dead {
+                continue;
+            }
+
+            if let Some(old) = worker.thread.take() {
+                let _ = old.join();
+            }
+
+            match Worker::new(worker.id, Arc::clone(&self.receiver), Arc::clone(&self.stats)) {
+                Ok(replacement) => {
+                    println!("Worker {} was not running; spawned a replacement.", worker.id);
+                    *worker = replacement;
+                }
+                Err(err) => {
+                    eprintln!("Failed to respawn worker {}: {err}", worker.id);
+                }
+            }
+        }
+    }
+
+    /// Number of workers currently in the pool.
+    pub fn size(&self) -> usize {
+        self.workers.lock().expect("Failed to acquire mutex").len()
+    }
+
+    /// Total number of jobs that have run to completion without panicking.
+    pub fn jobs_completed(&self) -> usize {
+        self.stats.jobs_completed.load(Ordering::Relaxed)
+    }
+
+    /// Total number of job panics caught and isolated to their worker.
+    pub fn panics_caught(&self) -> usize {
+        self.stats.panics_caught.load(Ordering::Relaxed)
+    }
+}
+
+impl Drop for ThreadPool {
+    fn drop(&mut self) {
+        let workers = self.workers.get_mut().expect("Failed to acquire mutex");
+
+        if let Some(sender) = &self.sender {
+            // Tell every worker to stop once it finishes whatever job it's
+            // currently holding; a worker blocked on recv() wakes up and sees
+            // this before the channel is closed below.
+            for _ in workers.iter() {
+                sender.send(Message::Terminate).unwrap();
+            }
+        }
+
+        // Closing the channel guarantees a worker that missed its Terminate
+        // message (e.g. never made it past the mutex) also breaks its loop.
+        drop(self.sender.take());
+
+        for worker in workers.iter_mut() {
+            println!("Shutting down worker {}", worker.id);
+
+            if let Some(t) = worker.thread.take() {
+                t.join().unwrap();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{sync::mpsc::channel, thread, time::Duration};
+
+    #[test]
+    fn build_rejects_zero_size() {
+        assert!(matches!(ThreadPool::build(0), Err(PoolCreationError::InvalidSize)));
+    }
+
+    #[test]
+    fn jobs_completed_tracks_successful_jobs() {
+        let pool = ThreadPool::build(2).unwrap();
+        let (tx, rx) = channel();
+
+        for _ in 0..4 {
+            let tx = tx.clone();
+            pool.execute(move || tx.send(()).unwrap());
+        }
+
+        for _ in 0..4 {
+            rx.recv_timeout(Duration::from_secs(1)).unwrap();
+        }
+        // Give the worker loop a moment to record the stat after sending.
+        thread::sleep(Duration::from_millis(50));
+
+        assert_eq!(pool.jobs_completed(), 4);
+        assert_eq!(pool.panics_caught(), 0);
+    }
+
+    #[test]
+    fn panicking_job_is_isolated_and_pool_stays_healthy() {
+        let pool = ThreadPool::build(2).unwrap();
+
+        pool.execute(|| panic!("boom"));
+
+        // Wait for the panic to be caught and counted, then confirm the pool
+        // still has its full worker count and can still run jobs.
+        thread::sleep(Duration::from_millis(100));
+        assert_eq!(pool.size(), 2);
+        assert_eq!(pool.panics_caught(), 1);
+
+        let (tx, rx) = channel();
+        pool.execute(move || tx.send(()).unwrap());
+        rx.recv_timeout(Duration::from_secs(1)).unwrap();
+    }
+
+    #[test]
+    fn ensure_capacity_respawns_a_worker_whose_thread_has_exited() {
+        let pool = ThreadPool::build(2).unwrap();
+
+        // Kill exactly one worker for real, not a job panic the loop already
+        // catches: send a Terminate message directly, bypassing Drop's
+        // shutdown sequence. Whichever worker pulls it off the queue first
+        // breaks out of its loop and exits, leaving the pool with a thread
+        // that has genuinely finished.
+        pool.sender.as_ref().unwrap().send(Message::Terminate).unwrap();
+        thread::sleep(Duration::from_millis(100));
+
+        let dead_id = {
+            let workers = pool.workers.lock().unwrap();
+            workers
+                .iter()
+                .find(|w| w.thread.as_ref().unwrap().is_finished())
+                .expect("one worker should have terminated")
+                .id
+        };
+
+        // The next execute() should notice the dead thread and respawn it.
+        let (tx, rx) = channel();
+        pool.execute(move || tx.send(()).unwrap());
+        rx.recv_timeout(Duration::from_secs(1)).unwrap();
+
+        assert_eq!(pool.size(), 2);
+        let workers = pool.workers.lock().unwrap();
+        let respawned = workers.iter().find(|w| w.id == dead_id).unwrap();
+        assert!(
+            !respawned.thread.as_ref().unwrap().is_finished(),
+            "expected worker {dead_id} to have been respawned with a live thread"
+        );
     }
 }
@@ -3,23 +3,73 @@
 //
 // [Motivation] With a single-threaded server, we can only handle one request at a time. If a request takes a long time to process, it will block all other requests.
 // To handle multiple requests at the same time, we need to use a multi-threaded server.
+#[cfg(not(feature = "async"))]
+use rusty_spinner::app;
+#[cfg(not(feature = "async"))]
+use rusty_spinner::router::Router;
+#[cfg(not(feature = "async"))]
+use rusty_spinner::static_files::StaticFiles;
+#[cfg(not(feature = "async"))]
+use rusty_spinner::ThreadPool;
+#[cfg(not(feature = "async"))]
 use std::{
-    fs,
-    io::{prelude::*, BufReader}, // Gives us access to traits and types that let us read from and write to the stream.
+    env,
     net::{TcpListener, TcpStream},
-    thread,
-    time::Duration,
+    sync::Arc,
 };
 
+// The `async` feature swaps the hand-rolled ThreadPool accept loop below for
+// the Tokio-driven one in `rusty_spinner::async_server`, so only one `main`
+// is compiled at a time.
+#[cfg(feature = "async")]
+#[tokio::main]
+async fn main() -> std::io::Result<()> {
+    rusty_spinner::async_server::run("127.0.0.1:7878", 100).await
+}
+
+#[cfg(not(feature = "async"))]
 fn main() {
     // Create a TCP listener on the specified address and port.
     // [Note] bind = connect. So we're connecting our listener to local host at port 7878.
     let listener = TcpListener::bind("127.0.0.1:7878").unwrap(); // bind returns a Result<T, E>. unwrap here will panic if an error occurs.
 
-    // [Note] We're using a for loop to accept incoming connections. The for loop will iterate over the incoming connections and handle each one.
-    // [Note] stream is is a Result<TcpStream, Error> type.
-    // [Note] The below will stay open until the program is terminated.
-    for stream in listener.incoming() {
+    // Dispatch every accepted connection onto the pool instead of handling it
+    // inline, so a slow handler (e.g. `/sleep`) no longer blocks every other
+    // request behind it.
+    let pool = ThreadPool::build(4).unwrap();
+
+    // The router and static file server are shared read-only across every
+    // worker thread, so they're built once up front and handed out behind an
+    // Arc. The document root is the current directory, where hello.html and
+    // 404.html already live. `app::build_router`/`build_static_files` are
+    // also used by the async server mode, so both modes serve identical
+    // routes.
+    let router = Arc::new(app::build_router());
+    let static_files: Arc<StaticFiles> = Arc::new(app::build_static_files());
+
+    // [Note] Setting SERVE_CONNECTIONS lets a caller (e.g. an integration test)
+    // ask the server to handle exactly N connections and then return, instead
+    // of running forever. Once `listener.incoming().take(n)` is exhausted,
+    // `pool` goes out of scope here and its `Drop` impl joins every worker
+    // after the last already-dequeued job finishes.
+    let connection_limit = env::var("SERVE_CONNECTIONS")
+        .ok()
+        .and_then(|n| n.parse::<usize>().ok());
+
+    match connection_limit {
+        Some(n) => serve(listener.incoming().take(n), &pool, &router, &static_files),
+        None => serve(listener.incoming(), &pool, &router, &static_files),
+    }
+}
+
+#[cfg(not(feature = "async"))]
+fn serve(
+    incoming: impl Iterator<Item = std::io::Result<TcpStream>>,
+    pool: &ThreadPool,
+    router: &Arc<Router>,
+    static_files: &Arc<StaticFiles>,
+) {
+    for stream in incoming {
         // [Note] Incoming returns an iterator that gives us a sequence of streams -  here eah stream (TcpStream) is a connection attempt.
         // [Note] You can test a connection using netcat from the CLI. nc -vz localhost 7878
         // [Note] A single stream represents an open connection between the client and the server.
@@ -29,54 +79,25 @@ fn main() {
         // [Note] a "handle" is an abstraction that represents an underlying resource, in this case, a TCP connection.
         //        It's a way for the program to interact with that resource without needing to know all the low-level details of how it's managed
         let stream = stream.unwrap();
-
-        // Utilize the stream handle for communication
-        handle_connection(stream);
-    }
-
-    fn handle_connection(mut stream: TcpStream) {
-        // Read from the TcpStream
-        let buf_reader = BufReader::new(&mut stream);
-        let request_line = buf_reader
-            .lines()
-            .next() // Returns an Option<Result<String>>
-            .unwrap() // unwrap the Option. [Note] In production, we would handle errors gracefully.
-            .unwrap(); // unwrap the Result
-
-        // If the user requests the root page, respond with a 200 OK status and the contents of hello.html.
-        let (status_line, filename) = match &request_line[..] {
-            "GET / HTTP/1.1" => ("HTTP/1.1 200 OK", "hello.html"),
-            "GET /sleep HTTP/1.1" => {
-                // Simulating a slow response
-                // Server sleep for 5 seconds before rendering the successful HTML page.
-                // As we are still single-threaded, any simultaneous requests will be queued and processed sequentially.
-                thread::sleep(Duration::from_secs(5));
-                ("HTTP/1.1 200 OK", "hello.html")
-            }
-            _ => ("HTTP/1.1 404 NOT FOUND", "404.html"),
-        };
-
-        let contents = fs::read_to_string(filename).unwrap();
-        let length = contents.len(); // [Note] len returns number of bytes in the string.
-
-        let log = format!(
-            "{status_line}\r\nContent-Type: text/html; charset=UTF-8\r\nContent-Length: {length}"
-        );
-
-        let response = format!("{status_line}\r\n\r\n{contents}");
-
-        println!("{log}"); // "console.log"
-        stream.write_all(response.as_bytes()).unwrap(); // Write the response to the stream.
+        let router = Arc::clone(router);
+        let static_files = Arc::clone(static_files);
+
+        // Hand the stream off to a worker thread instead of calling
+        // app::handle_connection directly on the accept loop's thread.
+        pool.execute(move || {
+            let mut stream = stream;
+            app::handle_connection(&mut stream, &router, &static_files);
+        });
     }
 }
 
 // _52766_276787664
 
-#[cfg(test)]
+#[cfg(all(test, not(feature = "async")))]
 mod tests {
     use super::*;
     use reqwest::blocking::Client;
-    use std::{thread, time::Duration, time::Instant};
+    use std::{fs, thread, time::Duration, time::Instant};
 
     /// Helper function to start the server in a background thread.
     fn start_server() {
@@ -183,4 +204,39 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn serve_stops_after_the_connection_limit_and_drops_the_listener() {
+        // Exercises the bounded "serve N then exit" mode `main` builds from
+        // `SERVE_CONNECTIONS` directly against `serve`, on an ephemeral port
+        // so it doesn't collide with the other tests' always-on servers on
+        // 7878.
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let handle = thread::spawn(move || {
+            let pool = ThreadPool::build(2).unwrap();
+            let router = Arc::new(app::build_router());
+            let static_files: Arc<StaticFiles> = Arc::new(app::build_static_files());
+
+            // `pool` is dropped when this closure returns, joining every
+            // worker after `serve` stops accepting.
+            serve(listener.incoming().take(2), &pool, &router, &static_files);
+        });
+
+        let client = Client::new();
+        for _ in 0..2 {
+            let resp = client.get(format!("http://{addr}/")).send().unwrap();
+            assert_eq!(resp.status(), 200, "Expected 200 for each of the first 2 connections");
+        }
+
+        handle.join().unwrap();
+
+        // The listener goes out of scope with the thread above, so the port
+        // is no longer accepting connections.
+        assert!(
+            TcpStream::connect(addr).is_err(),
+            "Expected the listener to be gone after the connection limit was reached"
+        );
+    }
 }
@@ -0,0 +1,84 @@
+// [Motivation] `handle_connection` used to dispatch on the request line with
+// a hardcoded `match`. `Router` lets callers register a handler per
+// method+path instead, so adding a route no longer means editing the
+// connection-handling code.
+use crate::http::{Method, Request, Response};
+use std::collections::HashMap;
+
+type Handler = Box<dyn Fn(&Request) -> Response + Send + Sync>;
+
+pub struct Router {
+    routes: HashMap<(Method, String), Handler>,
+}
+
+impl Router {
+    pub fn new() -> Router {
+        Router {
+            routes: HashMap::new(),
+        }
+    }
+
+    pub fn route<F>(&mut self, method: Method, path: &str, handler: F)
+    where
+        F: Fn(&Request) -> Response + Send + Sync + 'static,
+    {
+        self.routes.insert((method, path.to_string()), Box::new(handler));
+    }
+
+    // Returns `None` when no route matches, leaving it up to the caller to
+    // decide what a fallback (e.g. a 404 page) looks like.
+    pub fn handle(&self, request: &Request) -> Option<Response> {
+        self.routes
+            .get(&(request.method, request.path.clone()))
+            .map(|handler| handler(request))
+    }
+}
+
+impl Default for Router {
+    fn default() -> Router {
+        Router::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(method: Method, path: &str) -> Request {
+        Request {
+            method,
+            path: path.to_string(),
+            version: "HTTP/1.1".to_string(),
+            headers: Default::default(),
+            body: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn dispatches_to_the_matching_method_and_path() {
+        let mut router = Router::new();
+        router.route(Method::Get, "/hello", |_request| {
+            Response::new("HTTP/1.1 200 OK", b"hi".to_vec())
+        });
+
+        let response = router.handle(&request(Method::Get, "/hello")).unwrap();
+        assert_eq!(response.status_line, "HTTP/1.1 200 OK");
+        assert_eq!(response.body, b"hi");
+    }
+
+    #[test]
+    fn falls_back_to_none_for_an_unregistered_route() {
+        let mut router = Router::new();
+        router.route(Method::Get, "/hello", |_request| Response::new("HTTP/1.1 200 OK", Vec::new()));
+
+        assert!(router.handle(&request(Method::Get, "/missing")).is_none());
+    }
+
+    #[test]
+    fn distinguishes_routes_by_method() {
+        let mut router = Router::new();
+        router.route(Method::Get, "/thing", |_request| Response::new("HTTP/1.1 200 OK", Vec::new()));
+
+        assert!(router.handle(&request(Method::Post, "/thing")).is_none());
+    }
+}
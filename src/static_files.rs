@@ -0,0 +1,172 @@
+// [Motivation] `handle_connection` used to hardcode `fs::read_to_string("hello.html")`
+// and `"404.html"`. `StaticFiles` maps a request path onto a file under a
+// configured document root instead, rejecting any path that canonicalizes
+// outside that root so `GET /../../etc/passwd`-style traversal can't escape it.
+use crate::http::{Method, Request, Response};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+pub struct StaticFiles {
+    root: PathBuf,
+}
+
+enum Resolved {
+    Forbidden,
+    NotFound,
+}
+
+impl StaticFiles {
+    pub fn new(root: impl Into<PathBuf>) -> std::io::Result<StaticFiles> {
+        let root = root.into().canonicalize()?;
+        Ok(StaticFiles { root })
+    }
+
+    pub fn serve(&self, request: &Request) -> Response {
+        if request.method != Method::Get {
+            return Response::new("HTTP/1.1 405 METHOD NOT ALLOWED", Vec::new());
+        }
+
+        match self.resolve(&request.path) {
+            Ok(path) => match fs::read(&path) {
+                Ok(contents) => {
+                    Response::new("HTTP/1.1 200 OK", contents).with_header("Content-Type", content_type_for(&path))
+                }
+                Err(_) => self.not_found(),
+            },
+            Err(Resolved::Forbidden) => Response::new("HTTP/1.1 403 FORBIDDEN", Vec::new()),
+            Err(Resolved::NotFound) => self.not_found(),
+        }
+    }
+
+    fn not_found(&self) -> Response {
+        match fs::read(self.root.join("404.html")) {
+            Ok(contents) => Response::new("HTTP/1.1 404 NOT FOUND", contents),
+            Err(_) => Response::new("HTTP/1.1 404 NOT FOUND", Vec::new()),
+        }
+    }
+
+    // Joins `path` onto the document root and canonicalizes the result so
+    // `..` segments are resolved, then confirms the resolved path is still
+    // inside the root before letting the caller read it.
+    fn resolve(&self, path: &str) -> Result<PathBuf, Resolved> {
+        let relative = path.trim_start_matches('/');
+        let candidate = self.root.join(relative);
+
+        let resolved = candidate.canonicalize().map_err(|_| Resolved::NotFound)?;
+
+        if is_within_root(&self.root, &resolved) {
+            Ok(resolved)
+        } else {
+            Err(Resolved::Forbidden)
+        }
+    }
+
+    // The canonicalized document root, for callers (e.g. `async_server`)
+    // that need to resolve paths under it without going through `serve`.
+    #[cfg(feature = "async")]
+    pub(crate) fn root(&self) -> &Path {
+        &self.root
+    }
+}
+
+// Pulled out of `resolve` so `async_server` can apply the exact same
+// traversal check after canonicalizing a path its own way (via
+// `tokio::fs::canonicalize` instead of the blocking `Path::canonicalize`
+// above), without duplicating the logic.
+pub(crate) fn is_within_root(root: &Path, resolved: &Path) -> bool {
+    resolved.starts_with(root)
+}
+
+pub(crate) fn content_type_for(path: &Path) -> &'static str {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("html") => "text/html; charset=UTF-8",
+        Some("css") => "text/css",
+        Some("js") => "application/javascript",
+        Some("json") => "application/json",
+        Some("png") => "image/png",
+        Some("jpg") | Some("jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("svg") => "image/svg+xml",
+        Some("txt") => "text/plain",
+        _ => "application/octet-stream",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn request(method: Method, path: &str) -> Request {
+        Request {
+            method,
+            path: path.to_string(),
+            version: "HTTP/1.1".to_string(),
+            headers: Default::default(),
+            body: Vec::new(),
+        }
+    }
+
+    // A fresh document root per test, nested under a shared parent so the
+    // "outside the root" traversal test has somewhere real to escape to.
+    fn test_root() -> PathBuf {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        let id = COUNTER.fetch_add(1, Ordering::Relaxed);
+        let parent = std::env::temp_dir().join(format!("rusty-spinner-static-files-test-{}", std::process::id()));
+        let root = parent.join(format!("root-{id}"));
+        fs::create_dir_all(&root).unwrap();
+        root
+    }
+
+    #[test]
+    fn serves_a_file_with_the_matching_mime_type() {
+        let root = test_root();
+        fs::write(root.join("app.js"), b"console.log(1)").unwrap();
+        let static_files = StaticFiles::new(&root).unwrap();
+
+        let response = static_files.serve(&request(Method::Get, "/app.js"));
+
+        assert_eq!(response.status_line, "HTTP/1.1 200 OK");
+        assert_eq!(response.body, b"console.log(1)");
+        assert_eq!(
+            response.headers.get("Content-Type").map(String::as_str),
+            Some("application/javascript")
+        );
+    }
+
+    #[test]
+    fn returns_404_for_a_missing_file() {
+        let root = test_root();
+        fs::write(root.join("404.html"), b"not found here").unwrap();
+        let static_files = StaticFiles::new(&root).unwrap();
+
+        let response = static_files.serve(&request(Method::Get, "/missing.html"));
+
+        assert_eq!(response.status_line, "HTTP/1.1 404 NOT FOUND");
+        assert_eq!(response.body, b"not found here");
+    }
+
+    #[test]
+    fn rejects_path_traversal_outside_the_root_with_403() {
+        let root = test_root();
+        // A file that exists, but only outside the document root.
+        fs::write(root.parent().unwrap().join("secret.txt"), b"top secret").unwrap();
+        let static_files = StaticFiles::new(&root).unwrap();
+
+        let response = static_files.serve(&request(Method::Get, "/../secret.txt"));
+
+        assert_eq!(response.status_line, "HTTP/1.1 403 FORBIDDEN");
+    }
+
+    #[test]
+    fn rejects_non_get_methods_with_405() {
+        let root = test_root();
+        let static_files = StaticFiles::new(&root).unwrap();
+
+        let response = static_files.serve(&request(Method::Post, "/anything"));
+
+        assert_eq!(response.status_line, "HTTP/1.1 405 METHOD NOT ALLOWED");
+    }
+}